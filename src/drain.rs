@@ -1,13 +1,23 @@
+use std::heap::{Alloc, Heap};
 use std::marker::PhantomData;
+use std::ptr;
+use std::ptr::NonNull;
 
 use raw_val_iter::RawValIter;
-
-pub struct Drain<'a, T: 'a> {
-    _vec: PhantomData<&'a mut Vec<T>>,
+use vec::Vec;
+
+pub struct Drain<'a, T: 'a, A: Alloc + 'a = Heap> {
+    /// Index of the first element after the drained range, in the original
+    /// `Vec`. Used by `Drop` to know where the surviving tail starts.
+    tail_start: usize,
+    /// Number of elements after the drained range that must be preserved.
+    tail_len: usize,
+    vec: NonNull<Vec<T, A>>,
     iter: RawValIter<T>,
+    _marker: PhantomData<&'a mut Vec<T, A>>,
 }
 
-impl<'a, T> Iterator for Drain<'a, T> {
+impl<'a, T, A: Alloc> Iterator for Drain<'a, T, A> {
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
@@ -19,23 +29,52 @@ impl<'a, T> Iterator for Drain<'a, T> {
     }
 }
 
-impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+impl<'a, T, A: Alloc> DoubleEndedIterator for Drain<'a, T, A> {
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back()
     }
 }
 
-impl<'a, T> Drop for Drain<'a, T> {
+impl<'a, T, A: Alloc> Drop for Drain<'a, T, A> {
     fn drop(&mut self) {
+        // drop whatever the caller left unconsumed.
         for _ in &mut self.iter {}
+
+        if self.tail_len > 0 {
+            unsafe {
+                let source_vec = self.vec.as_mut();
+                let start = source_vec.len();
+                let tail = self.tail_start;
+
+                if tail != start {
+                    let src = source_vec.ptr().offset(tail as isize);
+                    let dst = source_vec.ptr().offset(start as isize);
+                    ptr::copy(src, dst, self.tail_len);
+                }
+
+                source_vec.set_len(start + self.tail_len);
+            }
+        }
     }
 }
 
-impl<'a, T> Drain<'a, T> {
-    pub(super) fn new(iter: RawValIter<T>) -> Self {
+impl<'a, T, A: Alloc> Drain<'a, T, A> {
+    // unsafe because `vec.len` must already have been lowered to the start
+    // of the drained range, `iter` must only yield elements of the range
+    // `[vec.len, tail_start)` of `*vec`, and `tail_len` elements must follow
+    // `tail_start`.
+    pub(super) unsafe fn new(
+        vec: &'a mut Vec<T, A>,
+        iter: RawValIter<T>,
+        tail_start: usize,
+        tail_len: usize,
+    ) -> Self {
         Drain {
-            _vec: PhantomData,
+            tail_start,
+            tail_len,
+            vec: NonNull::from(vec),
             iter,
+            _marker: PhantomData,
         }
     }
 }
@@ -45,13 +84,13 @@ mod tests {
     use vec::Vec;
 
     #[test]
-    fn start_0() {
+    fn full_range() {
         let mut v = Vec::default();
         v.push(0);
         v.push(1);
 
         {
-            let mut drain = v.drain(0);
+            let mut drain = v.drain(..);
             assert_eq!(drain.next(), Some(0));
             assert_eq!(drain.next(), Some(1));
             assert_eq!(drain.next(), None);
@@ -61,13 +100,13 @@ mod tests {
     }
 
     #[test]
-    fn start_0_back() {
+    fn full_range_back() {
         let mut v = Vec::default();
         v.push(0);
         v.push(1);
 
         {
-            let mut drain = v.drain(0);
+            let mut drain = v.drain(..);
             assert_eq!(drain.next_back(), Some(1));
             assert_eq!(drain.next_back(), Some(0));
             assert_eq!(drain.next_back(), None);
@@ -77,13 +116,13 @@ mod tests {
     }
 
     #[test]
-    fn start_1() {
+    fn tail() {
         let mut v = Vec::default();
         v.push(0);
         v.push(1);
 
         {
-            let mut drain = v.drain(1);
+            let mut drain = v.drain(1..);
             assert_eq!(drain.next(), Some(1));
             assert_eq!(drain.next(), None);
         }
@@ -96,13 +135,13 @@ mod tests {
     }
 
     #[test]
-    fn start_1_back() {
+    fn tail_back() {
         let mut v = Vec::default();
         v.push(0);
         v.push(1);
 
         {
-            let mut drain = v.drain(1);
+            let mut drain = v.drain(1..);
             assert_eq!(drain.next_back(), Some(1));
             assert_eq!(drain.next_back(), None);
         }
@@ -113,4 +152,59 @@ mod tests {
         assert_eq!(iter.next_back(), Some(0));
         assert_eq!(iter.next_back(), None);
     }
+
+    #[test]
+    fn mid_range_preserves_tail() {
+        let mut v = Vec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        {
+            let mut drain = v.drain(1..3);
+            assert_eq!(drain.next(), Some(1));
+            assert_eq!(drain.next(), Some(2));
+            assert_eq!(drain.next(), None);
+        }
+
+        assert_eq!(v.len(), 2);
+        assert_eq!(v.get(0), Some(&0));
+        assert_eq!(v.get(1), Some(&3));
+    }
+
+    #[test]
+    fn empty_range() {
+        let mut v = Vec::default();
+        v.push(0);
+        v.push(1);
+
+        {
+            let mut drain = v.drain(1..1);
+            assert_eq!(drain.next(), None);
+        }
+
+        assert_eq!(v.len(), 2);
+        assert_eq!(v.get(0), Some(&0));
+        assert_eq!(v.get(1), Some(&1));
+    }
+
+    #[test]
+    fn drop_without_exhausting() {
+        let mut v = Vec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        {
+            let mut drain = v.drain(1..3);
+            assert_eq!(drain.next(), Some(1));
+            // drop the rest unconsumed
+        }
+
+        assert_eq!(v.len(), 2);
+        assert_eq!(v.get(0), Some(&0));
+        assert_eq!(v.get(1), Some(&3));
+    }
 }
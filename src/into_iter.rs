@@ -1,14 +1,15 @@
+use std::heap::{Alloc, Heap};
 use std::mem;
 
 use raw_vec::RawVec;
 use raw_val_iter::RawValIter;
 
-pub struct IntoIter<T> {
-    _buf: RawVec<T>, // we don't actually care abount this. Just need it to live.
+pub struct IntoIter<T, A: Alloc = Heap> {
+    _buf: RawVec<T, A>, // we don't actually care abount this. Just need it to live.
     iter: RawValIter<T>,
 }
 
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Alloc> Iterator for IntoIter<T, A> {
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
@@ -20,13 +21,13 @@ impl<T> Iterator for IntoIter<T> {
     }
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Alloc> DoubleEndedIterator for IntoIter<T, A> {
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back()
     }
 }
 
-impl<T> Drop for IntoIter<T> {
+impl<T, A: Alloc> Drop for IntoIter<T, A> {
     fn drop(&mut self) {
         if mem::needs_drop::<T>() {
             for _ in &mut *self {}
@@ -36,8 +37,8 @@ impl<T> Drop for IntoIter<T> {
     }
 }
 
-impl<T> IntoIter<T> {
-    pub(super) fn new(buf: RawVec<T>, iter: RawValIter<T>) -> Self {
+impl<T, A: Alloc> IntoIter<T, A> {
+    pub(super) fn new(buf: RawVec<T, A>, iter: RawValIter<T>) -> Self {
         IntoIter { _buf: buf, iter }
     }
 }
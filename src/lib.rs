@@ -1,15 +1,78 @@
 #![feature(allocator_api)]
 #![feature(crate_in_paths)]
+#![feature(const_generics)]
+#![allow(incomplete_features)]
 
 #[cfg_attr(feature = "cargo-clippy", allow(should_implement_trait))]
 
+/// Creates a [`Vec`] containing the given elements, or `n` clones of a single
+/// element, much like the standard library's `vec!`.
+///
+/// [`Vec`]: vec/struct.Vec.html
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate nomicon_vec;
+///
+/// # fn main() {
+/// let v = vec![1, 2, 3];
+/// assert_eq!(&*v, &[1, 2, 3]);
+///
+/// let v = vec![0; 5];
+/// assert_eq!(&*v, &[0, 0, 0, 0, 0]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! vec {
+    () => {
+        $crate::vec::Vec::default()
+    };
+    ($elem:expr; $n:expr) => {
+        $crate::vec::from_elem($elem, $n)
+    };
+    ($($x:expr),+ $(,)?) => {{
+        let mut v = $crate::vec::Vec::default();
+        $(v.push($x);)+
+        v
+    }};
+}
+
 pub mod vec;
 pub mod into_iter;
 pub mod drain;
+pub mod small_vec;
 
 mod owned_ptr;
 mod raw_vec;
 mod raw_val_iter;
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use vec::Vec;
+
+    #[test]
+    fn vec_macro_empty() {
+        let v: Vec<i32> = vec![];
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn vec_macro_list() {
+        let v = vec![1, 2, 3];
+        assert_eq!(&*v, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn vec_macro_repeat() {
+        let v = vec![7; 3];
+        assert_eq!(&*v, &[7, 7, 7]);
+    }
+
+    #[test]
+    fn vec_macro_repeat_zero() {
+        let v: Vec<i32> = vec![7; 0];
+        assert!(v.is_empty());
+    }
+}
@@ -3,13 +3,13 @@ use std::mem;
 
 use owned_ptr::OwnedPtr;
 
-pub(super) struct RawVec<T> {
+pub(super) struct RawVec<T, A: Alloc = Heap> {
     pub(super) ptr: OwnedPtr<T>,
     pub(super) cap: usize,
-    alloc: Heap,
+    alloc: A,
 }
 
-impl<T> Drop for RawVec<T> {
+impl<T, A: Alloc> Drop for RawVec<T, A> {
     fn drop(&mut self) {
         let elem_size = mem::size_of::<T>();
 
@@ -31,48 +31,65 @@ impl<T> Drop for RawVec<T> {
     }
 }
 
-impl<T> RawVec<T> {
+impl<T> RawVec<T, Heap> {
     pub(super) fn default() -> Self {
+        RawVec::new_in(Heap)
+    }
+}
+
+impl<T, A: Alloc> RawVec<T, A> {
+    pub(super) fn new_in(alloc: A) -> Self {
         // !0 is usize::MAX. This branch should be stripped at compile time.
         let cap = if mem::size_of::<T>() == 0 { !0 } else { 0 };
 
         RawVec {
             ptr: OwnedPtr::empty(),
             cap,
-            alloc: Heap,
+            alloc,
         }
     }
 
-    pub(super) fn grow(&mut self) {
+    /// Ensures room for at least `needed_extra` more elements past the
+    /// `used_cap` already in use, growing in one step (to
+    /// `max(used_cap + needed_extra, cap * 2)`, the amortized-growth rule)
+    /// rather than repeatedly doubling.
+    pub(super) fn reserve(&mut self, used_cap: usize, needed_extra: usize) {
         let elem_size = mem::size_of::<T>();
 
-        // since we set the capacity to usize::MAX when elem_size is
-        // 0, getting to here necessarily means the Vec is overfull.
-        assert!(elem_size != 0, "capacity overflow");
-
-        let (ptr, new_cap) = if self.cap == 0 {
-            (self.alloc.alloc_one::<T>(), 1)
-        } else {
-            let old_num_bytes = self.cap * elem_size;
-
-            assert!(
-                old_num_bytes <= (::std::isize::MAX as usize) / 2,
-                "capacity overflow"
-            );
-
-            unsafe {
-                let new_cap = self.cap * 2;
-                let ptr = self.alloc
-                    .realloc_array::<T>(self.ptr.as_non_null(), self.cap, new_cap);
-                (ptr, new_cap)
-            }
-        };
+        // ZSTs have "infinite" capacity (see `new_in`) and never allocate.
+        if elem_size == 0 {
+            return;
+        }
 
-        if let Err(e) = ptr {
-            self.alloc.oom(e);
+        let required_cap = used_cap.checked_add(needed_extra).expect("capacity overflow");
+        if self.cap >= required_cap {
+            return;
         }
 
-        self.ptr = OwnedPtr::with_non_null(ptr.unwrap());
+        let new_cap = ::std::cmp::max(required_cap, self.cap * 2);
+
+        assert!(
+            new_cap
+                .checked_mul(elem_size)
+                .map_or(false, |n| n <= ::std::isize::MAX as usize),
+            "capacity overflow"
+        );
+
+        let ptr = match self.cap {
+            0 if new_cap == 1 => self.alloc.alloc_one::<T>(),
+            0 => self.alloc.alloc_array::<T>(new_cap),
+            old_cap => unsafe {
+                self.alloc
+                    .realloc_array::<T>(self.ptr.as_non_null(), old_cap, new_cap)
+            },
+        };
+
+        let ptr = match ptr {
+            Ok(ptr) => ptr,
+            Err(e) => self.alloc.oom(e),
+        };
+
+        self.ptr = OwnedPtr::with_non_null(ptr);
         self.cap = new_cap;
     }
 }
@@ -82,14 +99,43 @@ mod tests {
     use super::*;
 
     #[test]
-    fn grow() {
+    fn reserve_single_shot() {
         let mut v: RawVec<i32> = RawVec::default();
         assert_eq!(v.cap, 0);
 
-        for cap in (0..16).map(|c| (2 as usize).pow(c)) {
-            v.grow();
-            assert_eq!(v.cap, cap);
-        }
+        v.reserve(0, 5);
+        assert_eq!(v.cap, 5);
+
+        // already enough room: no-op.
+        v.reserve(3, 2);
+        assert_eq!(v.cap, 5);
+    }
+
+    #[test]
+    fn reserve_amortized_growth() {
+        let mut v: RawVec<i32> = RawVec::default();
+
+        v.reserve(0, 1);
+        assert_eq!(v.cap, 1);
+
+        v.reserve(1, 1);
+        assert_eq!(v.cap, 2);
+
+        v.reserve(2, 1);
+        assert_eq!(v.cap, 4);
+
+        // asking for more than double grows to exactly what's needed.
+        v.reserve(4, 10);
+        assert_eq!(v.cap, 14);
+    }
+
+    #[test]
+    fn reserve_zst_never_allocates() {
+        let mut v: RawVec<()> = RawVec::default();
+        assert_eq!(v.cap, !0);
+
+        v.reserve(0, 1_000_000);
+        assert_eq!(v.cap, !0);
     }
 
     #[test]
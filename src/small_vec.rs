@@ -0,0 +1,223 @@
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use raw_vec::RawVec;
+
+/// A vector that stores up to `N` elements inline, only spilling to the heap
+/// (through the crate-private `RawVec`) once it overflows.
+pub struct SmallVec<T, const N: usize>(SmallVecRepr<T, N>);
+
+// Kept private, and wrapped in the tuple struct above, so that `RawVec`
+// (`pub(super)`) never appears in `SmallVec`'s public API: a `pub enum` with
+// a `RawVec<T>` variant field would trip `private_interfaces`.
+enum SmallVecRepr<T, const N: usize> {
+    Inline { buf: [MaybeUninit<T>; N], len: usize },
+    Heap(RawVec<T>, usize),
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    /// Create a new `SmallVec` with no elements, stored inline.
+    pub fn default() -> Self {
+        SmallVec(SmallVecRepr::Inline {
+            // an array of `MaybeUninit` is always valid, regardless of `T`.
+            buf: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        match self.0 {
+            SmallVecRepr::Inline { len, .. } => len,
+            SmallVecRepr::Heap(_, len) => len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element to the last position, spilling the inline buffer
+    /// to the heap the first time it overflows.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v: nomicon_vec::small_vec::SmallVec<i32, 2> = nomicon_vec::small_vec::SmallVec::default();
+    ///
+    /// v.push(0);
+    /// v.push(1);
+    /// v.push(2); // spills to the heap here
+    ///
+    /// assert_eq!(&*v, &[0, 1, 2]);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        let elem = match self.0 {
+            SmallVecRepr::Inline {
+                ref mut buf,
+                ref mut len,
+            } if *len < N =>
+            {
+                buf[*len] = MaybeUninit::new(elem);
+                *len += 1;
+                return;
+            }
+            SmallVecRepr::Inline { .. } => elem,
+            SmallVecRepr::Heap(ref mut raw, ref mut len) => {
+                raw.reserve(*len, 1);
+                unsafe {
+                    ptr::write(raw.ptr.as_ptr().offset(*len as isize), elem);
+                }
+                *len += 1;
+                return;
+            }
+        };
+
+        self.spill_to_heap();
+        self.push(elem);
+    }
+
+    /// Moves the `N` inline elements into a freshly allocated `RawVec` and
+    /// transitions `self` to the `Heap` variant. No-op if already spilled.
+    fn spill_to_heap(&mut self) {
+        if let SmallVecRepr::Heap(..) = self.0 {
+            return;
+        }
+
+        let old = mem::replace(&mut self.0, SmallVecRepr::Heap(RawVec::default(), 0));
+
+        // `SmallVecRepr` itself has no `Drop` impl (only the outer
+        // `SmallVec` wrapper does) and `MaybeUninit<T>` never drops its
+        // contents, so moving `buf`/`len` out of `old` by value is fine.
+        if let SmallVecRepr::Inline { buf, len } = old {
+            let mut raw = RawVec::default();
+            raw.reserve(0, N);
+
+            unsafe {
+                ptr::copy_nonoverlapping(buf.as_ptr() as *const T, raw.ptr.as_ptr(), len);
+            }
+
+            self.0 = SmallVecRepr::Heap(raw, len);
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self.0 {
+            SmallVecRepr::Inline { ref buf, len } => unsafe {
+                ::std::slice::from_raw_parts(buf.as_ptr() as *const T, len)
+            },
+            SmallVecRepr::Heap(ref raw, len) => unsafe {
+                ::std::slice::from_raw_parts(raw.ptr.as_ptr(), len)
+            },
+        }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self.0 {
+            SmallVecRepr::Inline {
+                ref mut buf,
+                len,
+            } => unsafe { ::std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut T, len) },
+            SmallVecRepr::Heap(ref raw, len) => unsafe {
+                ::std::slice::from_raw_parts_mut(raw.ptr.as_ptr(), len)
+            },
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            let slice: *mut [T] = &mut **self;
+            ptr::drop_in_place(slice);
+        }
+
+        // heap deallocation, if `self` has spilled, is handled by `RawVec`'s
+        // own `Drop` impl; the inline array is never heap-allocated.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_inline() {
+        let mut v: SmallVec<i32, 4> = SmallVec::default();
+        v.push(0);
+        v.push(1);
+
+        assert_eq!(v.len(), 2);
+        assert_eq!(&*v, &[0, 1]);
+
+        if let SmallVecRepr::Heap(..) = v.0 {
+            panic!("should not have spilled");
+        }
+    }
+
+    #[test]
+    fn spills_to_heap() {
+        let mut v: SmallVec<i32, 2> = SmallVec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+
+        assert_eq!(v.len(), 3);
+        assert_eq!(&*v, &[0, 1, 2]);
+
+        if let SmallVecRepr::Inline { .. } = v.0 {
+            panic!("should have spilled");
+        }
+    }
+
+    #[test]
+    fn deref_mut() {
+        let mut v: SmallVec<i32, 4> = SmallVec::default();
+        v.push(0);
+        v.push(1);
+        v.reverse();
+
+        assert_eq!(&*v, &[1, 0]);
+    }
+
+    #[test]
+    fn spill_to_heap_is_idempotent() {
+        let mut v: SmallVec<i32, 2> = SmallVec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2); // spills
+
+        v.spill_to_heap(); // already spilled: must be a true no-op
+        assert_eq!(&*v, &[0, 1, 2]);
+    }
+
+    #[test]
+    fn drop_runs_for_inline_and_spilled() {
+        use std::cell::Cell;
+
+        thread_local!(static DROPS: Cell<u32> = Cell::new(0));
+
+        struct CountDrop;
+        impl Drop for CountDrop {
+            fn drop(&mut self) {
+                DROPS.with(|d| d.set(d.get() + 1));
+            }
+        }
+
+        {
+            let mut v: SmallVec<CountDrop, 1> = SmallVec::default();
+            v.push(CountDrop);
+            v.push(CountDrop); // spills
+        }
+
+        DROPS.with(|d| assert_eq!(d.get(), 2));
+    }
+}
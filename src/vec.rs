@@ -1,5 +1,6 @@
+use std::heap::{Alloc, Heap};
 use std::mem;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Bound, Deref, DerefMut, RangeBounds};
 use std::ptr;
 
 use raw_vec::RawVec;
@@ -7,12 +8,42 @@ use raw_val_iter::RawValIter;
 use into_iter::IntoIter;
 use drain::Drain;
 
-pub struct Vec<T> {
-    buf: RawVec<T>,
+pub struct Vec<T, A: Alloc = Heap> {
+    buf: RawVec<T, A>,
     len: usize,
 }
 
-impl<T> Deref for Vec<T> {
+/// Shared panic-safety guard for `retain`/`dedup`'s single-pass compaction:
+/// `[0, write)` holds the kept elements seen so far, `[write, read)` has
+/// already been shifted down or dropped, and `[read, original_len)` is the
+/// not-yet-visited tail. On drop, the tail is shifted down to sit right
+/// after `write` and `self.len` is restored to cover it, so a panic midway
+/// through `f`/`T::eq` preserves every element that hasn't been decided on
+/// (rather than leaking or double-dropping it).
+struct CompactionGuard<'a, T: 'a, A: Alloc + 'a> {
+    v: &'a mut Vec<T, A>,
+    read: usize,
+    write: usize,
+    original_len: usize,
+}
+
+impl<'a, T, A: Alloc> Drop for CompactionGuard<'a, T, A> {
+    fn drop(&mut self) {
+        let remaining = self.original_len - self.read;
+
+        unsafe {
+            if remaining > 0 && self.read != self.write {
+                let src = self.v.ptr().offset(self.read as isize);
+                let dst = self.v.ptr().offset(self.write as isize);
+                ptr::copy(src, dst, remaining);
+            }
+
+            self.v.set_len(self.write + remaining);
+        }
+    }
+}
+
+impl<T, A: Alloc> Deref for Vec<T, A> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -20,13 +51,13 @@ impl<T> Deref for Vec<T> {
     }
 }
 
-impl<T> DerefMut for Vec<T> {
+impl<T, A: Alloc> DerefMut for Vec<T, A> {
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe { ::std::slice::from_raw_parts_mut(self.ptr(), self.len) }
     }
 }
 
-impl<T> Drop for Vec<T> {
+impl<T, A: Alloc> Drop for Vec<T, A> {
     fn drop(&mut self) {
         if mem::needs_drop::<T>() {
             while let Some(_) = self.pop() {}
@@ -36,20 +67,72 @@ impl<T> Drop for Vec<T> {
     }
 }
 
-impl<T> Vec<T> {
+impl<T> Vec<T, Heap> {
     /// Create a new `Vec` with no elements.
     pub fn default() -> Self {
+        Vec::new_in(Heap)
+    }
+
+    /// Creates a new, empty `Vec` with space for at least `cap` elements
+    /// allocated up front, so the first `cap` pushes never reallocate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let v: nomicon_vec::vec::Vec<i32> = nomicon_vec::vec::Vec::with_capacity(10);
+    /// assert_eq!(v.len(), 0);
+    /// assert!(v.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(cap: usize) -> Self {
+        Vec::with_capacity_in(cap, Heap)
+    }
+}
+
+impl<T, A: Alloc> Vec<T, A> {
+    /// Creates a new, empty `Vec` backed by `alloc` instead of the default
+    /// [`Heap`] allocator.
+    ///
+    /// [`Heap`]: https://doc.rust-lang.org/std/heap/struct.Heap.html
+    pub fn new_in(alloc: A) -> Self {
         Vec {
-            buf: RawVec::default(),
+            buf: RawVec::new_in(alloc),
             len: 0,
         }
     }
 
+    /// Like [`with_capacity`], but backed by `alloc` instead of the default
+    /// [`Heap`] allocator.
+    ///
+    /// [`with_capacity`]: #method.with_capacity
+    /// [`Heap`]: https://doc.rust-lang.org/std/heap/struct.Heap.html
+    pub fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        let mut v = Vec::new_in(alloc);
+        v.buf.reserve(0, cap);
+        v
+    }
+
     /// Returns capacity.
     pub fn capacity(&self) -> usize {
         self.buf.cap
     }
 
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v: nomicon_vec::vec::Vec<i32> = nomicon_vec::vec::Vec::default();
+    /// v.reserve(10);
+    /// assert!(v.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.buf.reserve(self.len, additional);
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -74,9 +157,7 @@ impl<T> Vec<T> {
     /// assert_eq!(v.len(), 2);
     /// ```
     pub fn push(&mut self, elem: T) {
-        if self.len == self.capacity() {
-            self.buf.grow();
-        }
+        self.buf.reserve(self.len, 1);
 
         unsafe {
             let ptr_last = self.ptr().offset(self.len as isize);
@@ -141,9 +222,7 @@ impl<T> Vec<T> {
     pub fn insert(&mut self, index: usize, elem: T) {
         assert!(index <= self.len, "index out of bounds");
 
-        if self.len == self.capacity() {
-            self.buf.grow();
-        }
+        self.buf.reserve(self.len, 1);
 
         unsafe {
             if index < self.len {
@@ -195,10 +274,70 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Shortens the vector, dropping the excess elements in place.
+    ///
+    /// Does nothing if `len` is greater than or equal to the current length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v = nomicon_vec::vec::Vec::default();
+    /// v.push(0);
+    /// v.push(1);
+    /// v.push(2);
+    ///
+    /// v.truncate(1);
+    /// assert_eq!(&*v, &[0]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        unsafe {
+            let remaining_len = self.len - len;
+            let ptr_to_drop = self.ptr().offset(len as isize);
+
+            // lower `self.len` up front, so that if a `T::drop` below panics
+            // (`drop_in_place` still drops the rest of the slice while
+            // unwinding), `Vec::drop` won't drop the excised tail again.
+            self.len = len;
+
+            let to_drop = ::std::slice::from_raw_parts_mut(ptr_to_drop, remaining_len);
+            ptr::drop_in_place(to_drop);
+        }
+    }
+
+    /// Removes an element at a target index by swapping it with the last
+    /// element and popping, in O(1) but without preserving order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v = nomicon_vec::vec::Vec::default();
+    /// v.push(0);
+    /// v.push(1);
+    /// v.push(2);
+    ///
+    /// assert_eq!(v.swap_remove(0), 0);
+    /// assert_eq!(&*v, &[2, 1]);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        let last = self.len - 1;
+        self.swap(index, last);
+        self.pop().unwrap()
+    }
+
     /// Creates an [`IntoIter`] instance from self.
     ///
     /// [`IntoIter`]: ../into_iter/struct.IntoIter.html
-    pub fn into_iter(self) -> IntoIter<T> {
+    pub fn into_iter(self) -> IntoIter<T, A> {
         unsafe {
             let iter = RawValIter::new(&self);
 
@@ -209,19 +348,244 @@ impl<T> Vec<T> {
         }
     }
 
-    pub fn drain(&mut self, start: usize) -> Drain<T> {
-        assert!(start < self.len);
+    /// Removes the elements in `range` from the vector, returning them as an
+    /// iterator. Elements after `range` are preserved, shifted down to fill
+    /// the gap once the returned [`Drain`] is dropped.
+    ///
+    /// If the `Drain` is leaked, the elements after `range` may be left in an
+    /// unspecified (but safe) state.
+    ///
+    /// [`Drain`]: ../drain/struct.Drain.html
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v = nomicon_vec::vec::Vec::default();
+    /// v.push(0);
+    /// v.push(1);
+    /// v.push(2);
+    ///
+    /// assert_eq!(v.drain(1..).collect::<std::vec::Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+
+        assert!(start <= end, "drain start must be <= end");
+        assert!(end <= len, "drain end out of bounds");
 
         unsafe {
-            let iter = RawValIter::new(&self[start..]);
+            // set the length up front, so a panic/leak in the iterator or in
+            // user code can't expose the (possibly already-read) elements in
+            // the drained range.
             self.len = start;
-            Drain::new(iter)
+
+            let range_slice =
+                ::std::slice::from_raw_parts(self.ptr().offset(start as isize), end - start);
+            let iter = RawValIter::new(range_slice);
+
+            Drain::new(self, iter, end, len - end)
+        }
+    }
+
+    /// Retains only the elements for which `f` returns `true`, in a single
+    /// pass, shifting kept elements down over dropped ones.
+    ///
+    /// If `f` panics, the elements already decided on are left as they were
+    /// (kept ones moved down, rejected ones dropped) and the not-yet-visited
+    /// tail is shifted down and kept alive, rather than leaked or
+    /// double-dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v = nomicon_vec::vec::Vec::default();
+    /// v.push(0);
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    ///
+    /// v.retain(|&x| x % 2 == 0);
+    /// assert_eq!(&*v, &[0, 2]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let original_len = self.len;
+
+        // lower `self.len` up front: a panic anywhere below must not expose
+        // a half-compacted vec. `CompactionGuard` restores it on drop.
+        unsafe {
+            self.set_len(0);
+        }
+
+        let mut g = CompactionGuard {
+            v: self,
+            read: 0,
+            write: 0,
+            original_len,
+        };
+
+        while g.read < g.original_len {
+            unsafe {
+                let cur = g.v.ptr().offset(g.read as isize);
+
+                if !f(&*cur) {
+                    // advance `read` *before* dropping: if the drop panics,
+                    // the guard must not revisit this already-dropped slot.
+                    g.read += 1;
+                    ptr::drop_in_place(cur);
+                    continue;
+                }
+
+                if g.write != g.read {
+                    ptr::copy_nonoverlapping(cur, g.v.ptr().offset(g.write as isize), 1);
+                }
+                g.write += 1;
+                g.read += 1;
+            }
         }
     }
 
-    fn ptr(&self) -> *mut T {
+    /// Removes consecutive duplicate elements, keeping the first of each run.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate nomicon_vec;
+    ///
+    /// let mut v = nomicon_vec::vec::Vec::default();
+    /// v.push(0);
+    /// v.push(0);
+    /// v.push(1);
+    /// v.push(1);
+    /// v.push(1);
+    /// v.push(0);
+    ///
+    /// v.dedup();
+    /// assert_eq!(&*v, &[0, 1, 0]);
+    /// ```
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        let original_len = self.len;
+        if original_len < 2 {
+            return;
+        }
+
+        // lower `self.len` up front, same as `retain`: `CompactionGuard`
+        // restores it on drop, so a panicking `T::eq` can't expose a
+        // half-compacted vec or cause a double-drop.
+        unsafe {
+            self.set_len(0);
+        }
+
+        // index 0 is never a duplicate of a "previous" element, so it's
+        // always kept; start past it.
+        let mut g = CompactionGuard {
+            v: self,
+            read: 1,
+            write: 1,
+            original_len,
+        };
+
+        while g.read < g.original_len {
+            unsafe {
+                let cur = g.v.ptr().offset(g.read as isize);
+                let prev = g.v.ptr().offset((g.write - 1) as isize);
+
+                if *cur == *prev {
+                    // advance `read` *before* dropping: if the drop panics,
+                    // the guard must not revisit this already-dropped slot.
+                    g.read += 1;
+                    ptr::drop_in_place(cur);
+                    continue;
+                }
+
+                if g.write != g.read {
+                    ptr::copy_nonoverlapping(cur, g.v.ptr().offset(g.write as isize), 1);
+                }
+                g.write += 1;
+                g.read += 1;
+            }
+        }
+    }
+
+    pub(super) fn ptr(&self) -> *mut T {
         self.buf.ptr.as_ptr()
     }
+
+    pub(super) unsafe fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+}
+
+impl<T, A: Alloc> IntoIterator for Vec<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        Vec::into_iter(self)
+    }
+}
+
+impl<T, A: Alloc> Extend<T> for Vec<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+
+        let (lower, _) = iter.size_hint();
+        self.buf.reserve(self.len, lower);
+
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T> ::std::iter::FromIterator<T> for Vec<T, Heap> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Vec::default();
+        v.extend(iter);
+        v
+    }
+}
+
+/// Used by the `vec![elem; n]` macro form: reserves `n` slots up front, then
+/// clones `elem` into each one. If a user `Clone` impl panics partway
+/// through, only the elements already written are dropped.
+#[doc(hidden)]
+pub fn from_elem<T: Clone>(elem: T, n: usize) -> Vec<T> {
+    let mut v = Vec::with_capacity(n);
+
+    if n == 0 {
+        return v;
+    }
+
+    for _ in 0..n - 1 {
+        v.push(elem.clone());
+    }
+    v.push(elem);
+
+    v
 }
 
 #[cfg(test)]
@@ -242,6 +606,53 @@ mod tests {
         assert_eq!(v.remove(0), ());
     }
 
+    #[test]
+    fn with_capacity() {
+        let v: Vec<i32> = Vec::with_capacity(10);
+        assert_eq!(v.len(), 0);
+        assert_eq!(v.capacity(), 10);
+    }
+
+    #[test]
+    fn new_in_heap() {
+        let mut v = Vec::new_in(::std::heap::Heap);
+        v.push(0);
+        v.push(1);
+        assert_eq!(v.pop(), Some(1));
+    }
+
+    #[test]
+    fn extend() {
+        let mut v = Vec::default();
+        v.push(0);
+        v.extend(vec![1, 2, 3]);
+
+        assert_eq!(&*v, &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn from_iter() {
+        let v: Vec<i32> = ::std::iter::FromIterator::from_iter(0..3);
+        assert_eq!(&*v, &[0, 1, 2]);
+    }
+
+    #[test]
+    fn collect() {
+        let v: Vec<i32> = (0..3).collect();
+        assert_eq!(&*v, &[0, 1, 2]);
+    }
+
+    #[test]
+    fn reserve() {
+        let mut v: Vec<i32> = Vec::default();
+        v.reserve(10);
+        assert_eq!(v.capacity(), 10);
+
+        v.push(0);
+        v.reserve(9);
+        assert_eq!(v.capacity(), 10);
+    }
+
     #[test]
     #[should_panic]
     fn insert_panic_0() {
@@ -292,4 +703,144 @@ mod tests {
         assert_eq!(v.pop(), Some(0));
         assert_eq!(v.pop(), Some(1));
     }
+
+    #[test]
+    fn truncate() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+
+        v.truncate(1);
+        assert_eq!(&*v, &[0]);
+
+        // no-op when `len` is already large enough.
+        v.truncate(5);
+        assert_eq!(&*v, &[0]);
+    }
+
+    #[test]
+    fn swap_remove() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+
+        assert_eq!(v.swap_remove(0), 0);
+        assert_eq!(&*v, &[2, 1]);
+
+        assert_eq!(v.swap_remove(1), 1);
+        assert_eq!(&*v, &[2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_remove_panic() {
+        let mut v: Vec<i32> = Vec::default();
+        v.swap_remove(0);
+    }
+
+    #[test]
+    fn retain() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v.push(4);
+
+        v.retain(|&x| x % 2 == 0);
+        assert_eq!(&*v, &[0, 2, 4]);
+    }
+
+    #[test]
+    fn retain_none() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(1);
+
+        v.retain(|_| false);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn retain_all() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(1);
+
+        v.retain(|_| true);
+        assert_eq!(&*v, &[0, 1]);
+    }
+
+    #[test]
+    fn dedup() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(0);
+        v.push(1);
+        v.push(1);
+        v.push(1);
+        v.push(0);
+
+        v.dedup();
+        assert_eq!(&*v, &[0, 1, 0]);
+    }
+
+    #[test]
+    fn dedup_no_dups() {
+        let mut v: Vec<i32> = Vec::default();
+        v.push(0);
+        v.push(1);
+        v.push(2);
+
+        v.dedup();
+        assert_eq!(&*v, &[0, 1, 2]);
+    }
+
+    #[test]
+    fn retain_panic_preserves_tail_and_drops_exactly_once() {
+        use std::cell::Cell;
+        use std::panic::{self, AssertUnwindSafe};
+
+        thread_local!(static DROPS: Cell<u32> = Cell::new(0));
+
+        struct CountDrop(i32);
+        impl Drop for CountDrop {
+            fn drop(&mut self) {
+                DROPS.with(|d| d.set(d.get() + 1));
+            }
+        }
+
+        let mut v: Vec<CountDrop> = Vec::default();
+        for i in 0..5 {
+            v.push(CountDrop(i));
+        }
+
+        // element 0 is kept, element 1 is dropped, element 2 panics before
+        // `f` returns (and before it's dropped); 3 and 4 are the
+        // not-yet-visited tail.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.retain(|x| {
+                if x.0 == 2 {
+                    panic!("boom");
+                }
+                x.0 % 2 == 0
+            });
+        }));
+        assert!(result.is_err());
+
+        // `CompactionGuard` should have shifted the untouched tail (2, 3, 4)
+        // down over the dropped slot rather than leaking or double-dropping
+        // it, leaving exactly one drop so far (element 1).
+        assert_eq!(v.len(), 4);
+        assert_eq!(
+            v.iter().map(|x| x.0).collect::<::std::vec::Vec<_>>(),
+            [0, 2, 3, 4]
+        );
+        DROPS.with(|d| assert_eq!(d.get(), 1));
+
+        drop(v);
+        DROPS.with(|d| assert_eq!(d.get(), 5));
+    }
 }